@@ -1,345 +1,600 @@
 use sourcemap::SourceMap;
+use swc_common::{BytePos, Span, Spanned};
 use swc_ecma_ast::{
-    BlockStmt, Decl, DoWhileStmt, Expr, ExprStmt, ForInStmt, ForOfStmt, ForStmt, IfStmt,
-    LabeledStmt, Pat, ReturnStmt, Script, Stmt, SwitchStmt, ThrowStmt, TryStmt, VarDecl,
-    VarDeclOrExpr, VarDeclOrPat, WhileStmt, WithStmt,
+    ArrowExpr, DoWhileStmt, Expr, ExprOrSuper, ExprStmt, FnDecl, FnExpr, ForInStmt, ForOfStmt,
+    ForStmt, Lit, Prop, PropName, PropOrSpread, Script, Stmt, UnaryOp, WhileStmt,
 };
 
-use super::{ExpressionList, Lintable};
+use super::visitor::{
+    walk_arrow_expr, walk_do_while_stmt, walk_expr, walk_expr_stmt, walk_fn_decl, walk_fn_expr,
+    walk_for_in_stmt, walk_for_of_stmt, walk_for_stmt, walk_while_stmt,
+};
+use super::{Diagnostic, DiagnosticSink, ExpressionList, Level, Visitor};
 
 // the difference between the args for linting a Script and linting an AstNode
 // is that the script doesn't need to know whether or not it's in the request context,
-// because it's always *not* in the request context. It does, however, take an optional
-// source map that can be used to map errors to the original source to provide more
-// helpful error messages to developers
-type ScriptLinterArgs<'a> = (Option<&'a SourceMap>, ExpressionList, ExpressionList);
-type AstNodeLinterArgs<'a> = (bool, &'a ExpressionList, &'a ExpressionList);
+// because it's always *not* in the request context. It does, however, take the
+// original source text and an optional source map, both used to map errors back
+// to the original source to provide more helpful error messages to developers
+type ScriptLinterArgs<'a> = (&'a str, Option<&'a SourceMap>, ExpressionList, ExpressionList);
+
+/// A table of byte offsets where each line of `source` starts, built once per
+/// lint run so a `swc_common::BytePos` can be turned into a `(line, column)`
+/// pair: a 1-based line and a 0-based byte column. `sourcemap::SourceMap::lookup_token`
+/// takes both 0-based, so callers of `line_col` should subtract 1 from the line
+/// before using it.
+///
+/// This assumes `source` was parsed as the only file swc knows about, so a
+/// `BytePos` is a plain offset into it -- true for how we invoke the parser,
+/// but worth knowing if that ever changes.
+struct LineIndex {
+    line_starts: Vec<u32>,
+}
 
-impl<'a> Lintable<ScriptLinterArgs<'a>> for Script {
-    fn lint(
-        &self,
-        (source_map, unavailable, available_in_request_context): ScriptLinterArgs,
-    ) -> Result<(), failure::Error> {
-        if let Err(error) = self
-            .body
-            .lint((false, &unavailable, &available_in_request_context))
-        {
-            Err(match source_map {
-                Some(map) => match_error_to_source_map(error, map)?,
-                None => error,
-            })
-        } else {
-            Ok(())
-        }
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(
+            source
+                .char_indices()
+                .filter(|(_, character)| *character == '\n')
+                .map(|(offset, _)| offset as u32 + 1),
+        );
+        LineIndex { line_starts }
+    }
+
+    fn line_col(&self, pos: BytePos) -> (u32, u32) {
+        let offset = pos.0;
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        };
+        (line as u32 + 1, offset - self.line_starts[line])
+    }
+}
+
+/// Lints an entire script, returning every diagnostic collected along the way.
+/// This is the entry point the rest of the build pipeline calls: it runs a
+/// single [`LintVisitor`] pass over the AST, resolves every diagnostic's span
+/// back to the original source, then fails the build if any diagnostic is
+/// `Deny`-level -- a `Warn` is just feedback, but a script that actually
+/// touches an unavailable API can't be allowed to ship.
+pub fn lint_script(
+    script: &Script,
+    (source, source_map, unavailable, available_in_request_context): ScriptLinterArgs,
+) -> Result<DiagnosticSink, failure::Error> {
+    let mut visitor = LintVisitor::new(&unavailable, &available_in_request_context);
+    visitor.visit_script(script);
+    let mut sink = visitor.sink;
+
+    let lines = LineIndex::new(source);
+    for diagnostic in &mut sink {
+        let (line, column) = lines.line_col(diagnostic.span.lo());
+        diagnostic.message = match_error_to_source_map(&diagnostic.message, line, column, source_map);
     }
+
+    if sink.iter().any(Diagnostic::is_fatal) {
+        return Err(failure::format_err!(
+            "{}",
+            sink.iter()
+                .map(|diagnostic| diagnostic.message.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+
+    Ok(sink)
 }
 
-// TODO: it would be cool to have line numbers in the errors
-// and i don't think it would be like extremely hard to do,
-// since every statement has its own associated byte position.
-// But that's a nice-to-have for sure
 fn match_error_to_source_map(
-    error: failure::Error,
-    source_map: &SourceMap,
-) -> Result<failure::Error, failure::Error> {
-    Ok(failure::format_err!("Thanks for providing us with a source map! Soon hopefully we will be able to tell you what part of your original source code is bad. Unfortunately, for now, all we can say is\n{}", error))
+    message: &str,
+    line: u32,
+    column: u32,
+    source_map: Option<&SourceMap>,
+) -> String {
+    let token = source_map.and_then(|map| map.lookup_token(line - 1, column));
+
+    match token {
+        Some(token) => format!(
+            "{}:{}:{}: {}",
+            token.get_source().unwrap_or("<generated>"),
+            token.get_src_line() + 1,
+            token.get_src_col() + 1,
+            token.get_name().map(|name| format!("({}) ", name)).unwrap_or_default() + message,
+        ),
+        None => format!("{}:{}: {}", line, column, message),
+    }
 }
 
-// TODO all of these need to also take a reference to what's available / unavailable
+/// Walks a whole script in one pass, reporting every problem it finds along
+/// the way instead of stopping at the first one. This used to be a dozen
+/// hand-written `Lintable` impls, each recursing into its own children; now
+/// each check just overrides the `Visitor` methods it cares about, and
+/// everything else is inherited for free from the default `walk_*` bodies.
+struct LintVisitor<'a> {
+    in_request_context: bool,
+    unavailable: &'a ExpressionList,
+    available_in_request_context: &'a ExpressionList,
+    sink: DiagnosticSink,
+}
 
-/// By implementing Lintable for Vec<Stmt>, we can call `ast.lint(false)`
-/// at the top level and recurse through the whole AST
-///
-/// Note: Ideally, the type signature would actually be more general,
-/// `impl<'a, T> Lintable<AstNodeLinterArgs<'a>> for T where T: Iterator<Item = dyn Lintable<AstNodeLinterArgs<'a>>>,`
-/// but rustc is not happy about us implementing this when swc might potentially
-/// implement Iterator for e.g. Stmt. Then we'd have conflicting implementations
-/// of Lintable for any struct that also implemented Iterator.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for Vec<Stmt> {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        // this would be cool if it was par_iter...rayon when?
-        self.iter().try_for_each(|statement| statement.lint(args))
+impl<'a> LintVisitor<'a> {
+    fn new(unavailable: &'a ExpressionList, available_in_request_context: &'a ExpressionList) -> Self {
+        LintVisitor {
+            in_request_context: false,
+            unavailable,
+            available_in_request_context,
+            sink: DiagnosticSink::new(),
+        }
+    }
+
+    /// Runs `walk` with `in_request_context` forced to `true`, restoring
+    /// whatever it was before once `walk` returns. Used when entering the body
+    /// of a function -- arrow, expression, or declaration -- since that body
+    /// only actually runs once a request comes in, even if it's defined at the
+    /// top level of the script.
+    fn in_request_context(&mut self, walk: impl FnOnce(&mut Self)) {
+        let previous = self.in_request_context;
+        self.in_request_context = true;
+        walk(self);
+        self.in_request_context = previous;
     }
 }
 
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for Stmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        // tremendous shoutout to MDN, shame they shut it down
-        match self {
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/block
-            Stmt::Block(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/Empty
-            Stmt::Empty(_) => Ok(()),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/debugger
-            Stmt::Debugger(_) => Ok(()),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/with
-            Stmt::With(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/return
-            Stmt::Return(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/label
-            Stmt::Labeled(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/break
-            Stmt::Break(_) => Ok(()),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/continue
-            Stmt::Continue(_) => Ok(()),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/if...else
-            Stmt::If(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/switch
-            Stmt::Switch(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/throw
-            Stmt::Throw(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/try...catch
-            Stmt::Try(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/while
-            Stmt::While(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/do...while
-            Stmt::DoWhile(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/for
-            Stmt::For(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/for...in
-            Stmt::ForIn(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/for...of
-            Stmt::ForOf(statement) => statement.lint(args),
-            // https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements#Declarations
-            Stmt::Decl(statement) => statement.lint(args),
-            // i suppose all expressions are technically statements?
-            Stmt::Expr(statement) => statement.lint(args),
+impl<'a> Visitor for LintVisitor<'a> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let Some(path) = flatten_member_path(expr) {
+            check_api_availability(
+                &path,
+                expr.span(),
+                self.in_request_context,
+                self.unavailable,
+                self.available_in_request_context,
+                &mut self.sink,
+            );
         }
+
+        walk_expr(self, expr);
     }
-}
 
-/// [Block statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/block)
-/// are just any block of code in between some
-/// curly braces, so we can treat them like a mini-AST and just
-/// lint all of their child statements.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for BlockStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.stmts.lint(args)
+    fn visit_arrow_expr(&mut self, arrow_expr: &ArrowExpr) {
+        self.in_request_context(|visitor| walk_arrow_expr(visitor, arrow_expr));
     }
-}
 
-/// [With statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/with)
-/// are...deprecated? I personally have never seen them used, but it acts just like a with
-/// statement in Python -- it exposes whatever is in the with expression to its child scope
-/// ```ignore
-/// var a, x, y;
-/// var r = 10;
-///
-/// with (Math) {
-///   a = PI * r * r;
-///   x = r * cos(PI);
-///   y = r * sin(PI / 2);
-/// }
-/// ```
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for WithStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.obj.lint(args)?;
-        self.body.lint(args)?;
-        Ok(())
+    fn visit_fn_expr(&mut self, fn_expr: &FnExpr) {
+        self.in_request_context(|visitor| walk_fn_expr(visitor, fn_expr));
+    }
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.in_request_context(|visitor| walk_fn_decl(visitor, fn_decl));
     }
-}
 
-/// [Return statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/return)
-/// can either return an expression or nothing. If they return an expression, we need to lint it.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for ReturnStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        if let Some(expression) = &self.arg {
-            expression.lint(args)
-        } else {
-            Ok(())
+    fn visit_expr_stmt(&mut self, expr_stmt: &ExprStmt) {
+        walk_expr_stmt(self, expr_stmt);
+
+        // directive prologue entries (e.g. "use strict";) are bare string literals
+        // with a real effect on the engine, so they're exempt from this check
+        if !is_directive_prologue(&expr_stmt.expr) && is_pure(&expr_stmt.expr) {
+            self.sink.push(Diagnostic::new(
+                Level::Warn,
+                "expression's result is unused; this statement has no effect",
+                expr_stmt.span(),
+            ));
         }
     }
-}
 
-/// [Labeled statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/label)
-/// allow for break of continue statements to refer to their target with a label
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for LabeledStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.body.lint(args)
+    fn visit_while_stmt(&mut self, while_stmt: &WhileStmt) {
+        walk_while_stmt(self, while_stmt);
+        lint_needless_continue(&while_stmt.body, while_stmt.span(), &mut self.sink);
+    }
+
+    fn visit_do_while_stmt(&mut self, do_while_stmt: &DoWhileStmt) {
+        walk_do_while_stmt(self, do_while_stmt);
+        lint_needless_continue(&do_while_stmt.body, do_while_stmt.span(), &mut self.sink);
+    }
+
+    fn visit_for_stmt(&mut self, for_stmt: &ForStmt) {
+        walk_for_stmt(self, for_stmt);
+        lint_needless_continue(&for_stmt.body, for_stmt.span(), &mut self.sink);
+    }
+
+    fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) {
+        walk_for_in_stmt(self, for_in_stmt);
+        lint_needless_continue(&for_in_stmt.body, for_in_stmt.span(), &mut self.sink);
+    }
+
+    fn visit_for_of_stmt(&mut self, for_of_stmt: &ForOfStmt) {
+        walk_for_of_stmt(self, for_of_stmt);
+        lint_needless_continue(&for_of_stmt.body, for_of_stmt.span(), &mut self.sink);
     }
 }
 
-/// [If statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/if...else)
-/// contain a test expression, which needs to be linted, and a consequent body that gets executed if the statement is
-/// true -- which also needs to be linted. Optionally, they may contain an `else` clause, which also also
-/// needs to be linted.
-///
-/// Not entirely sure how this handled multiple `if else` statements, but I'm sure it's fine.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for IfStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.test.lint(args)?;
-        self.cons.lint(args)?;
-
-        if let Some(else_statement) = &self.alt {
-            else_statement.lint(args)
-        } else {
-            Ok(())
-        }
+/// Adapted from clippy's [`needless_continue`](https://rust-lang.github.io/rust-clippy/master/index.html#needless_continue)
+/// lint: flags loop bodies that use an explicit, unlabeled `continue` where
+/// inverting a condition would read more directly. Two shapes are checked --
+/// a trailing `if cond { .. } else { continue; }`, and a leading
+/// `if cond { continue; }` guard clause -- both of which collapse into a
+/// single inverted `if` with no `continue` at all.
+fn lint_needless_continue(body: &Stmt, loop_span: Span, sink: &mut DiagnosticSink) {
+    let block = match body {
+        Stmt::Block(block) => block,
+        _ => return,
+    };
+
+    let ends_with_continue_else = matches!(
+        block.stmts.last(),
+        Some(Stmt::If(if_stmt)) if if_stmt.alt.as_deref().map_or(false, is_solely_continue)
+    );
+
+    if ends_with_continue_else {
+        sink.push(Diagnostic::new(
+            Level::Warn,
+            "this `if`'s `else` branch is just a `continue` -- consider inverting the condition and dropping the `else`",
+            loop_span,
+        ));
+    }
+
+    let starts_with_continue_guard = matches!(
+        block.stmts.first(),
+        Some(Stmt::If(if_stmt)) if if_stmt.alt.is_none() && is_solely_continue(&if_stmt.cons)
+    );
+
+    if starts_with_continue_guard {
+        sink.push(Diagnostic::new(
+            Level::Warn,
+            "this loop starts with a guard clause that `continue`s -- consider inverting the condition and dropping the `continue`",
+            loop_span,
+        ));
     }
 }
 
-/// [Switch statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/switch)
-/// contain a discriminant expression, which needs to be linted, and a bunch of cases, which also need
-/// to be linted. Every one of these cases except for `default` contains a test expression, which needs to be linted.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for SwitchStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.discriminant.lint(args)?;
-        self.cases.iter().try_for_each(|case| {
-            if let Some(expression) = &case.test {
-                expression.lint(args)?;
-            };
-            case.cons.lint(args)
-        })
+/// Whether `stmt` is nothing but an unlabeled `continue`, with or without
+/// the braces of a block around it.
+fn is_solely_continue(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Continue(continue_stmt) => continue_stmt.label.is_none(),
+        Stmt::Block(block) => matches!(
+            block.stmts.as_slice(),
+            [Stmt::Continue(continue_stmt)] if continue_stmt.label.is_none()
+        ),
+        _ => false,
     }
 }
 
-/// [Throw statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/throw)
-/// have an expression that they throw, which needs to be linted.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for ThrowStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.arg.lint(args)
+fn is_directive_prologue(expr: &Expr) -> bool {
+    matches!(expr, Expr::Lit(Lit::Str(_)))
+}
+
+/// Modeled on rustc's `unused_must_use`/`unused_results`: an expression is "pure" if
+/// evaluating it can't do anything observable, which means nothing in its subtree is
+/// a call, construction, assignment, update, `delete`, `await`, `yield`, or tagged
+/// template invocation. We don't look inside function/class/arrow bodies here --
+/// merely writing one down has no effect regardless of what it does once (if ever)
+/// it's actually invoked.
+fn is_pure(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(_)
+        | Expr::New(_)
+        | Expr::Assign(_)
+        | Expr::Update(_)
+        | Expr::Await(_)
+        | Expr::Yield(_)
+        | Expr::TaggedTpl(_) => false,
+        Expr::Member(member) => {
+            let object_is_pure = match &member.obj {
+                ExprOrSuper::Expr(object) => is_pure(object),
+                ExprOrSuper::Super(_) => true,
+            };
+            object_is_pure && (!member.computed || is_pure(&member.prop))
+        }
+        Expr::Bin(binary) => is_pure(&binary.left) && is_pure(&binary.right),
+        // `delete` always has the observable effect of removing a property, no
+        // matter how "pure" the expression it's applied to looks
+        Expr::Unary(unary) => unary.op != UnaryOp::Delete && is_pure(&unary.arg),
+        Expr::Cond(conditional) => {
+            is_pure(&conditional.test) && is_pure(&conditional.cons) && is_pure(&conditional.alt)
+        }
+        Expr::Seq(sequence) => sequence.exprs.iter().all(|expr| is_pure(expr)),
+        Expr::Paren(parenthesized) => is_pure(&parenthesized.expr),
+        Expr::Tpl(template) => template.exprs.iter().all(|expr| is_pure(expr)),
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .all(|element| element.as_ref().map_or(true, |element| is_pure(&element.expr))),
+        Expr::Object(object) => object.props.iter().all(|prop| match prop {
+            PropOrSpread::Spread(spread) => is_pure(&spread.expr),
+            PropOrSpread::Prop(prop) => {
+                let key_is_pure = |key: &PropName| match key {
+                    PropName::Computed(computed) => is_pure(&computed.expr),
+                    _ => true,
+                };
+                match &**prop {
+                    Prop::Shorthand(_) => true,
+                    Prop::KeyValue(key_value) => {
+                        key_is_pure(&key_value.key) && is_pure(&key_value.value)
+                    }
+                    // only the key is evaluated here; the body runs when (if ever) it's invoked
+                    Prop::Getter(getter) => key_is_pure(&getter.key),
+                    Prop::Setter(setter) => key_is_pure(&setter.key),
+                    Prop::Method(method) => key_is_pure(&method.key),
+                    Prop::Assign(_) => false,
+                }
+            }
+        }),
+        _ => true,
     }
 }
 
-/// [Try statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/try...catch)
-/// contain a block of code inside the `try {}`, which needs to be linted. Optionally, there may also be
-/// a `catch {}` clause, which needs to be linted. If the `catch` is catching something specific, that
-/// expression also needs to be linted. Finally, if there's a `finally`, the content of that statement
-/// needs to be linted, too.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for TryStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        // lint the stuff inside try {}
-        self.block.lint(args)?;
-
-        // lint the stuff inside catch {}, if there is one
-        if let Some(clause) = &self.handler {
-            clause.body.lint(args)?;
-
-            // lint the specifically caught error, if it exists
-            // TODO: do we actually need to do this?
-            if let Some(pattern) = &clause.param {
-                pattern.lint(args)?;
+/// Flattens a chain of member accesses (and the identifier at its root) into the
+/// dotted path it represents, e.g. `caches.default` or `crypto.subtle.digest`.
+///
+/// Returns `None` when the expression isn't a static dotted path -- a computed
+/// member access like `foo[bar]` or a non-identifier receiver can't be resolved
+/// to a fixed name, so we can't check it against an `ExpressionList`.
+fn flatten_member_path(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::Member(member) => {
+            if member.computed {
+                return None;
             }
-        };
 
-        // lint the finally {}, if it exists
-        if let Some(finally) = &self.finalizer {
-            finally.lint(args)
-        } else {
-            Ok(())
+            let object = match &member.obj {
+                ExprOrSuper::Expr(expression) => flatten_member_path(expression)?,
+                ExprOrSuper::Super(_) => return None,
+            };
+
+            let property = match &*member.prop {
+                Expr::Ident(ident) => ident.sym.to_string(),
+                _ => return None,
+            };
+
+            Some(format!("{}.{}", object, property))
         }
+        _ => None,
     }
 }
 
-/// [While statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/while)
-/// test to see if a condition is true, and executes a block if it is. Both the test and the block
-/// need to be linted.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for WhileStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.test.lint(args)?;
-        self.body.lint(args)
+/// Checks a resolved dotted path (see `flatten_member_path`) against the
+/// `unavailable` and `available_in_request_context` lists, pushing a
+/// diagnostic at whatever level the matching list entry carries. An API that's
+/// in `unavailable` is always reported; one that's only in
+/// `available_in_request_context` is reported unless we're currently linting
+/// code that runs inside a request.
+fn check_api_availability(
+    path: &str,
+    span: Span,
+    in_request_context: bool,
+    unavailable: &ExpressionList,
+    available_in_request_context: &ExpressionList,
+    sink: &mut DiagnosticSink,
+) {
+    if let Some(level) = unavailable.level(path) {
+        sink.push(Diagnostic::new(
+            level,
+            format!("`{}` is not available to Workers scripts", path),
+            span,
+        ));
+        return;
     }
-}
 
-///[Do-While statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/do...while)
-/// function the same as `while` statements, except that the test comes after the block, guaranteeing the
-/// block is run at least once, even if the condition evaluates to false.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for DoWhileStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.test.lint(args)?;
-        self.body.lint(args)
+    if !in_request_context {
+        if let Some(level) = available_in_request_context.level(path) {
+            sink.push(Diagnostic::new(
+                level,
+                format!(
+                    "`{}` is only available inside a request handler, not at the top level of your script",
+                    path
+                ),
+                span,
+            ));
+        }
     }
 }
 
-/// [For statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/for)
-/// contain several elements that need linting. Consider the following:
-/// ```ignore
-/// for(let i = 0; i < arr.len; i++) {
-///     // do stuff
-/// }
-/// ```
-/// * the entire `for ... {}` block refers to the ForStmt
-/// * the `let i = 0` expression, or initializer, needs to be linted
-/// * the `i < arr.len` expression, or test, needs to be linted
-/// * the i++ expression, or update, needs to be linted
-/// * the contents of the block need to be linted
-///
-/// Due to the loose nature of javascript, many of these elements are optional, hence
-/// the usage of `match` and `if let Some` statements.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for ForStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        match &self.init {
-            Some(VarDeclOrExpr::VarDecl(declaration)) => declaration.lint(args),
-            Some(VarDeclOrExpr::Expr(expression)) => expression.lint(args),
-            None => Ok(()),
-        }?;
-
-        if let Some(expression) = &self.test {
-            expression.lint(args)?
-        };
+#[cfg(test)]
+mod tests {
+    use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
 
-        if let Some(expression) = &self.update {
-            expression.lint(args)?
-        };
+    use super::*;
+    use crate::build::check::js::Level;
 
-        self.body.lint(args)
+    fn parse(source: &str) -> Script {
+        let input = StringInput::new(source, BytePos(0), BytePos(source.len() as u32));
+        let lexer = Lexer::new(Syntax::Es(Default::default()), Default::default(), input, None);
+        Parser::new_from(lexer)
+            .parse_script()
+            .expect("test source should parse as a script")
     }
-}
 
-/// [For...in statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/for...in)
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for ForInStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.left.lint(args)?;
-        self.right.lint(args)?;
-        self.body.lint(args)
+    fn lint(
+        source: &str,
+        unavailable: ExpressionList,
+        available_in_request_context: ExpressionList,
+    ) -> Result<DiagnosticSink, failure::Error> {
+        let script = parse(source);
+        lint_script(&script, (source, None, unavailable, available_in_request_context))
     }
-}
 
-/// [For...of statements](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/for...of)
-/// function similarly to `for...in` statements, except for objects instead of arrays.
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for ForOfStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.left.lint(args)?;
-        self.right.lint(args)?;
-        self.body.lint(args)
+    #[test]
+    fn resolves_diagnostic_message_through_a_source_map() {
+        let mut builder = sourcemap::SourceMapBuilder::new(None);
+        let src_id = builder.add_source("original.js");
+        builder.set_source_contents(src_id, Some("original source"));
+        // generated line 1 ("line - 1" == 0), column 9 maps back to original.js:5:3
+        builder.add(0, 9, 4, 2, Some("original.js"), None);
+        let map = builder.into_sourcemap();
+
+        let message = match_error_to_source_map("oops", 1, 9, Some(&map));
+
+        assert_eq!(message, "original.js:5:3: oops");
     }
-}
 
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for ExprStmt {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        self.expr.lint(args)
+    #[test]
+    fn falls_back_to_the_generated_position_without_a_source_map() {
+        let message = match_error_to_source_map("oops", 3, 7, None);
+
+        assert_eq!(message, "3:7: oops");
     }
-}
 
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for Expr {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        todo!()
+    #[test]
+    fn fails_the_build_when_a_deny_level_api_is_referenced() {
+        let unavailable = ExpressionList::new(Level::Deny, vec!["document.write"]);
+        let available_in_request_context = ExpressionList::new(Level::Warn, Vec::<&str>::new());
+
+        let result = lint("document.write('hi');", unavailable, available_in_request_context);
+
+        assert!(result.is_err());
     }
-}
 
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for Decl {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        todo!()
+    #[test]
+    fn does_not_fail_the_build_for_warn_level_diagnostics_alone() {
+        let unavailable = ExpressionList::new(Level::Warn, vec!["document.write"]);
+        let available_in_request_context = ExpressionList::new(Level::Warn, Vec::<&str>::new());
+
+        let diagnostics = lint("document.write('hi');", unavailable, available_in_request_context)
+            .expect("a Warn-only diagnostic shouldn't fail the build");
+
+        assert_eq!(diagnostics.len(), 1);
     }
-}
 
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for Pat {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        todo!()
+    #[test]
+    fn denies_a_deeply_nested_unavailable_api_reference() {
+        let unavailable = ExpressionList::new(Level::Deny, vec!["crypto.subtle.digest"]);
+        let available_in_request_context = ExpressionList::new(Level::Warn, Vec::<&str>::new());
+
+        let result = lint(
+            "crypto.subtle.digest('SHA-256', data);",
+            unavailable,
+            available_in_request_context,
+        );
+
+        assert!(result.is_err());
     }
-}
 
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for VarDecl {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        todo!()
+    #[test]
+    fn warns_on_a_request_context_only_api_used_at_the_top_level() {
+        let unavailable = ExpressionList::new(Level::Deny, Vec::<&str>::new());
+        let available_in_request_context = ExpressionList::new(Level::Warn, vec!["caches.default"]);
+
+        let diagnostics = lint("caches.default.match(request);", unavailable, available_in_request_context)
+            .expect("a Warn-only diagnostic shouldn't fail the build");
+
+        assert_eq!(diagnostics.len(), 1);
     }
-}
 
-impl<'a> Lintable<AstNodeLinterArgs<'a>> for VarDeclOrPat {
-    fn lint(&self, args: AstNodeLinterArgs) -> Result<(), failure::Error> {
-        match self {
-            VarDeclOrPat::VarDecl(declaration) => declaration.lint(args),
-            VarDeclOrPat::Pat(pattern) => pattern.lint(args),
-        }
+    #[test]
+    fn does_not_warn_on_a_request_context_only_api_used_inside_a_function_body() {
+        let unavailable = ExpressionList::new(Level::Deny, Vec::<&str>::new());
+        let available_in_request_context = ExpressionList::new(Level::Warn, vec!["caches.default"]);
+
+        let diagnostics = lint(
+            "function handle(request) { return caches.default.match(request); }",
+            unavailable,
+            available_in_request_context,
+        )
+        .expect("a Warn-only diagnostic shouldn't fail the build");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn denies_an_unavailable_api_reference_inside_a_tagged_template() {
+        let unavailable = ExpressionList::new(Level::Deny, vec!["document.write"]);
+        let available_in_request_context = ExpressionList::new(Level::Warn, Vec::<&str>::new());
+
+        let result = lint(
+            "someTag`<div>${document.write('x')}</div>`;",
+            unavailable,
+            available_in_request_context,
+        );
+
+        assert!(result.is_err());
+    }
+
+    fn lint_unused_expressions(source: &str) -> DiagnosticSink {
+        lint(
+            source,
+            ExpressionList::new(Level::Deny, Vec::<&str>::new()),
+            ExpressionList::new(Level::Warn, Vec::<&str>::new()),
+        )
+        .expect("these sources don't reference any unavailable API")
+    }
+
+    #[test]
+    fn does_not_warn_on_a_delete_expression_statement() {
+        let diagnostics = lint_unused_expressions("delete cache.entry;");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn warns_on_an_object_literal_with_no_side_effects() {
+        let diagnostics = lint_unused_expressions("({ a: 1 });");
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn does_not_warn_on_an_object_literal_containing_a_call() {
+        let diagnostics = lint_unused_expressions("({ a: compute() });");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn does_not_warn_on_a_tagged_template() {
+        let diagnostics = lint_unused_expressions("tag`${sideEffect()}`;");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    fn lint_loop(source: &str) -> DiagnosticSink {
+        lint(
+            source,
+            ExpressionList::new(Level::Deny, Vec::<&str>::new()),
+            ExpressionList::new(Level::Warn, Vec::<&str>::new()),
+        )
+        .expect("these sources don't reference any unavailable API")
+    }
+
+    #[test]
+    fn warns_on_a_trailing_if_else_continue() {
+        let diagnostics = lint_loop(
+            "for (const item of items) {
+                if (item.valid) {
+                    use(item);
+                } else {
+                    continue;
+                }
+            }",
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn warns_on_a_leading_continue_guard_clause() {
+        let diagnostics = lint_loop(
+            "while (true) {
+                if (!item.valid) {
+                    continue;
+                }
+                use(item);
+            }",
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn does_not_warn_on_a_loop_body_without_a_needless_continue() {
+        let diagnostics = lint_loop(
+            "for (const item of items) {
+                use(item);
+            }",
+        );
+
+        assert!(diagnostics.is_empty());
     }
 }
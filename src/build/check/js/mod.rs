@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use swc_common::Span;
+
+mod linter;
+mod visitor;
+
+pub use linter::lint_script;
+pub use visitor::Visitor;
+
+/// The severity of a [`Diagnostic`], borrowed from rustc/clippy's lint-level model.
+/// `Allow` is kept around for completeness even though nothing emits it yet --
+/// it's the natural place to land an entry a user wants to silence in the future.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// A single problem found while linting a script. `message` is meant to be shown
+/// to the developer as-is; `level` decides whether it's just feedback (`Warn`) or
+/// something that should stop the build (`Deny`). `span` is the offending node's
+/// position in the generated/bundled source the linter actually walked, and it
+/// stays that way: before handing diagnostics back to the caller, `lint_script`
+/// prefixes `message` with the original position resolved through a
+/// `sourcemap::SourceMap` (or with the generated `line:column` when there is no
+/// map or no matching token).
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub level: Level,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn new(level: Level, message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            message: message.into(),
+            level,
+            span,
+        }
+    }
+
+    pub fn is_fatal(&self) -> bool {
+        self.level == Level::Deny
+    }
+}
+
+/// Where every check pushes the problems it finds as it walks the AST (see
+/// [`Visitor`]), instead of returning on the first one and leaving the rest
+/// of the script unchecked.
+pub type DiagnosticSink = Vec<Diagnostic>;
+
+/// A named set of dotted API paths (e.g. `caches.default`, `crypto.subtle.digest`).
+/// Every entry carries a [`Level`]: a list is built with a default level for all
+/// of its entries, but individual entries can be given a different one with
+/// [`ExpressionList::with_level`] -- e.g. most of `unavailable` is `Deny`, but a
+/// soft-deprecated API can still be downgraded to `Warn`.
+#[derive(Debug, Clone)]
+pub struct ExpressionList {
+    entries: HashMap<String, Level>,
+}
+
+impl ExpressionList {
+    pub fn new(default_level: Level, entries: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        ExpressionList {
+            entries: entries
+                .into_iter()
+                .map(|entry| (entry.into(), default_level))
+                .collect(),
+        }
+    }
+
+    pub fn with_level(mut self, path: impl Into<String>, level: Level) -> Self {
+        self.entries.insert(path.into(), level);
+        self
+    }
+
+    /// Returns the level a reference to `path` should be diagnosed at, or
+    /// `None` if `path` isn't in this list at all.
+    pub(crate) fn level(&self, path: &str) -> Option<Level> {
+        self.entries.get(path).copied()
+    }
+}
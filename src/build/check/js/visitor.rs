@@ -0,0 +1,408 @@
+//! A generic AST walker modeled on `rustc_ast`'s visitor pattern. Every node we
+//! know how to walk gets one overridable `visit_*` method on [`Visitor`], whose
+//! default implementation just calls the matching free `walk_*` function to
+//! recurse into its children. A check used to need a hand-written `Lintable`
+//! impl for every node type it cared about, even if all it wanted to do was
+//! recurse; now it only overrides the handful of `visit_*` methods it actually
+//! has something to say about; `walk_*` is called from inside a Visitor so
+//! implementors of Visitor can override any of its methods.
+
+use swc_ecma_ast::{
+    ArrowExpr, BlockStmt, BlockStmtOrExpr, Decl, DoWhileStmt, Expr, ExprOrSuper, ExprStmt,
+    FnDecl, FnExpr, ForInStmt, ForOfStmt, ForStmt, IfStmt, LabeledStmt, ObjectPatProp, Pat,
+    PatOrExpr, ReturnStmt, Script, Stmt, SwitchStmt, ThrowStmt, TryStmt, VarDecl, VarDeclOrExpr,
+    VarDeclOrPat, WhileStmt, WithStmt,
+};
+
+/// Calls `$visitor.$method(item)` for every `item` in `$list` -- a small stand-in
+/// for the handful of places a node holds a `Vec` of children that each need
+/// their own `visit_*` call (e.g. `BlockStmt.stmts`).
+macro_rules! walk_list {
+    ($visitor:expr, $method:ident, $list:expr) => {
+        for item in $list {
+            $visitor.$method(item);
+        }
+    };
+}
+
+pub trait Visitor {
+    fn visit_script(&mut self, script: &Script) {
+        walk_script(self, script)
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        walk_stmt(self, stmt)
+    }
+
+    fn visit_block_stmt(&mut self, block: &BlockStmt) {
+        walk_block_stmt(self, block)
+    }
+
+    fn visit_with_stmt(&mut self, with_stmt: &WithStmt) {
+        walk_with_stmt(self, with_stmt)
+    }
+
+    fn visit_return_stmt(&mut self, return_stmt: &ReturnStmt) {
+        walk_return_stmt(self, return_stmt)
+    }
+
+    fn visit_labeled_stmt(&mut self, labeled_stmt: &LabeledStmt) {
+        walk_labeled_stmt(self, labeled_stmt)
+    }
+
+    fn visit_if_stmt(&mut self, if_stmt: &IfStmt) {
+        walk_if_stmt(self, if_stmt)
+    }
+
+    fn visit_switch_stmt(&mut self, switch_stmt: &SwitchStmt) {
+        walk_switch_stmt(self, switch_stmt)
+    }
+
+    fn visit_throw_stmt(&mut self, throw_stmt: &ThrowStmt) {
+        walk_throw_stmt(self, throw_stmt)
+    }
+
+    fn visit_try_stmt(&mut self, try_stmt: &TryStmt) {
+        walk_try_stmt(self, try_stmt)
+    }
+
+    fn visit_while_stmt(&mut self, while_stmt: &WhileStmt) {
+        walk_while_stmt(self, while_stmt)
+    }
+
+    fn visit_do_while_stmt(&mut self, do_while_stmt: &DoWhileStmt) {
+        walk_do_while_stmt(self, do_while_stmt)
+    }
+
+    fn visit_for_stmt(&mut self, for_stmt: &ForStmt) {
+        walk_for_stmt(self, for_stmt)
+    }
+
+    fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) {
+        walk_for_in_stmt(self, for_in_stmt)
+    }
+
+    fn visit_for_of_stmt(&mut self, for_of_stmt: &ForOfStmt) {
+        walk_for_of_stmt(self, for_of_stmt)
+    }
+
+    fn visit_expr_stmt(&mut self, expr_stmt: &ExprStmt) {
+        walk_expr_stmt(self, expr_stmt)
+    }
+
+    fn visit_decl(&mut self, decl: &Decl) {
+        walk_decl(self, decl)
+    }
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        walk_fn_decl(self, fn_decl)
+    }
+
+    fn visit_var_decl(&mut self, var_decl: &VarDecl) {
+        walk_var_decl(self, var_decl)
+    }
+
+    fn visit_var_decl_or_pat(&mut self, var_decl_or_pat: &VarDeclOrPat) {
+        walk_var_decl_or_pat(self, var_decl_or_pat)
+    }
+
+    fn visit_pat(&mut self, pat: &Pat) {
+        walk_pat(self, pat)
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr)
+    }
+
+    fn visit_arrow_expr(&mut self, arrow_expr: &ArrowExpr) {
+        walk_arrow_expr(self, arrow_expr)
+    }
+
+    fn visit_fn_expr(&mut self, fn_expr: &FnExpr) {
+        walk_fn_expr(self, fn_expr)
+    }
+}
+
+pub fn walk_script<V: Visitor + ?Sized>(visitor: &mut V, script: &Script) {
+    walk_list!(visitor, visit_stmt, &script.body);
+}
+
+pub fn walk_stmt<V: Visitor + ?Sized>(visitor: &mut V, stmt: &Stmt) {
+    match stmt {
+        Stmt::Block(block) => visitor.visit_block_stmt(block),
+        Stmt::Empty(_) | Stmt::Debugger(_) | Stmt::Break(_) | Stmt::Continue(_) => {}
+        Stmt::With(with_stmt) => visitor.visit_with_stmt(with_stmt),
+        Stmt::Return(return_stmt) => visitor.visit_return_stmt(return_stmt),
+        Stmt::Labeled(labeled_stmt) => visitor.visit_labeled_stmt(labeled_stmt),
+        Stmt::If(if_stmt) => visitor.visit_if_stmt(if_stmt),
+        Stmt::Switch(switch_stmt) => visitor.visit_switch_stmt(switch_stmt),
+        Stmt::Throw(throw_stmt) => visitor.visit_throw_stmt(throw_stmt),
+        Stmt::Try(try_stmt) => visitor.visit_try_stmt(try_stmt),
+        Stmt::While(while_stmt) => visitor.visit_while_stmt(while_stmt),
+        Stmt::DoWhile(do_while_stmt) => visitor.visit_do_while_stmt(do_while_stmt),
+        Stmt::For(for_stmt) => visitor.visit_for_stmt(for_stmt),
+        Stmt::ForIn(for_in_stmt) => visitor.visit_for_in_stmt(for_in_stmt),
+        Stmt::ForOf(for_of_stmt) => visitor.visit_for_of_stmt(for_of_stmt),
+        Stmt::Decl(decl) => visitor.visit_decl(decl),
+        Stmt::Expr(expr_stmt) => visitor.visit_expr_stmt(expr_stmt),
+    }
+}
+
+pub fn walk_block_stmt<V: Visitor + ?Sized>(visitor: &mut V, block: &BlockStmt) {
+    walk_list!(visitor, visit_stmt, &block.stmts);
+}
+
+pub fn walk_with_stmt<V: Visitor + ?Sized>(visitor: &mut V, with_stmt: &WithStmt) {
+    visitor.visit_expr(&with_stmt.obj);
+    visitor.visit_stmt(&with_stmt.body);
+}
+
+pub fn walk_return_stmt<V: Visitor + ?Sized>(visitor: &mut V, return_stmt: &ReturnStmt) {
+    if let Some(argument) = &return_stmt.arg {
+        visitor.visit_expr(argument);
+    }
+}
+
+pub fn walk_labeled_stmt<V: Visitor + ?Sized>(visitor: &mut V, labeled_stmt: &LabeledStmt) {
+    visitor.visit_stmt(&labeled_stmt.body);
+}
+
+pub fn walk_if_stmt<V: Visitor + ?Sized>(visitor: &mut V, if_stmt: &IfStmt) {
+    visitor.visit_expr(&if_stmt.test);
+    visitor.visit_stmt(&if_stmt.cons);
+    if let Some(alt) = &if_stmt.alt {
+        visitor.visit_stmt(alt);
+    }
+}
+
+pub fn walk_switch_stmt<V: Visitor + ?Sized>(visitor: &mut V, switch_stmt: &SwitchStmt) {
+    visitor.visit_expr(&switch_stmt.discriminant);
+    for case in &switch_stmt.cases {
+        if let Some(test) = &case.test {
+            visitor.visit_expr(test);
+        }
+        walk_list!(visitor, visit_stmt, &case.cons);
+    }
+}
+
+pub fn walk_throw_stmt<V: Visitor + ?Sized>(visitor: &mut V, throw_stmt: &ThrowStmt) {
+    visitor.visit_expr(&throw_stmt.arg);
+}
+
+pub fn walk_try_stmt<V: Visitor + ?Sized>(visitor: &mut V, try_stmt: &TryStmt) {
+    visitor.visit_block_stmt(&try_stmt.block);
+
+    if let Some(handler) = &try_stmt.handler {
+        visitor.visit_block_stmt(&handler.body);
+
+        if let Some(pattern) = &handler.param {
+            visitor.visit_pat(pattern);
+        }
+    }
+
+    if let Some(finalizer) = &try_stmt.finalizer {
+        visitor.visit_block_stmt(finalizer);
+    }
+}
+
+pub fn walk_while_stmt<V: Visitor + ?Sized>(visitor: &mut V, while_stmt: &WhileStmt) {
+    visitor.visit_expr(&while_stmt.test);
+    visitor.visit_stmt(&while_stmt.body);
+}
+
+pub fn walk_do_while_stmt<V: Visitor + ?Sized>(visitor: &mut V, do_while_stmt: &DoWhileStmt) {
+    visitor.visit_expr(&do_while_stmt.test);
+    visitor.visit_stmt(&do_while_stmt.body);
+}
+
+pub fn walk_for_stmt<V: Visitor + ?Sized>(visitor: &mut V, for_stmt: &ForStmt) {
+    match &for_stmt.init {
+        Some(VarDeclOrExpr::VarDecl(declaration)) => visitor.visit_var_decl(declaration),
+        Some(VarDeclOrExpr::Expr(expression)) => visitor.visit_expr(expression),
+        None => {}
+    }
+
+    if let Some(test) = &for_stmt.test {
+        visitor.visit_expr(test);
+    }
+
+    if let Some(update) = &for_stmt.update {
+        visitor.visit_expr(update);
+    }
+
+    visitor.visit_stmt(&for_stmt.body);
+}
+
+pub fn walk_for_in_stmt<V: Visitor + ?Sized>(visitor: &mut V, for_in_stmt: &ForInStmt) {
+    visitor.visit_var_decl_or_pat(&for_in_stmt.left);
+    visitor.visit_expr(&for_in_stmt.right);
+    visitor.visit_stmt(&for_in_stmt.body);
+}
+
+pub fn walk_for_of_stmt<V: Visitor + ?Sized>(visitor: &mut V, for_of_stmt: &ForOfStmt) {
+    visitor.visit_var_decl_or_pat(&for_of_stmt.left);
+    visitor.visit_expr(&for_of_stmt.right);
+    visitor.visit_stmt(&for_of_stmt.body);
+}
+
+pub fn walk_expr_stmt<V: Visitor + ?Sized>(visitor: &mut V, expr_stmt: &ExprStmt) {
+    visitor.visit_expr(&expr_stmt.expr);
+}
+
+pub fn walk_decl<V: Visitor + ?Sized>(visitor: &mut V, decl: &Decl) {
+    match decl {
+        Decl::Var(var_decl) => visitor.visit_var_decl(var_decl),
+        Decl::Fn(fn_decl) => visitor.visit_fn_decl(fn_decl),
+        // TODO: class declarations and TS-only declarations (interfaces, type
+        // aliases, enums, modules) aren't walked yet
+        _ => {}
+    }
+}
+
+pub fn walk_fn_decl<V: Visitor + ?Sized>(visitor: &mut V, fn_decl: &FnDecl) {
+    if let Some(body) = &fn_decl.function.body {
+        visitor.visit_block_stmt(body);
+    }
+}
+
+pub fn walk_var_decl<V: Visitor + ?Sized>(visitor: &mut V, var_decl: &VarDecl) {
+    for declarator in &var_decl.decls {
+        visitor.visit_pat(&declarator.name);
+
+        if let Some(init) = &declarator.init {
+            visitor.visit_expr(init);
+        }
+    }
+}
+
+pub fn walk_var_decl_or_pat<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    var_decl_or_pat: &VarDeclOrPat,
+) {
+    match var_decl_or_pat {
+        VarDeclOrPat::VarDecl(declaration) => visitor.visit_var_decl(declaration),
+        VarDeclOrPat::Pat(pattern) => visitor.visit_pat(pattern),
+    }
+}
+
+pub fn walk_pat<V: Visitor + ?Sized>(visitor: &mut V, pat: &Pat) {
+    match pat {
+        Pat::Ident(_) | Pat::Invalid(_) => {}
+        Pat::Array(array) => {
+            for element in array.elems.iter().flatten() {
+                visitor.visit_pat(element);
+            }
+        }
+        Pat::Object(object) => {
+            for prop in &object.props {
+                match prop {
+                    ObjectPatProp::KeyValue(key_value) => visitor.visit_pat(&key_value.value),
+                    ObjectPatProp::Assign(assign) => {
+                        if let Some(default) = &assign.value {
+                            visitor.visit_expr(default);
+                        }
+                    }
+                    ObjectPatProp::Rest(rest) => visitor.visit_pat(&rest.arg),
+                }
+            }
+        }
+        Pat::Assign(assign) => {
+            visitor.visit_pat(&assign.left);
+            visitor.visit_expr(&assign.right);
+        }
+        Pat::Rest(rest) => visitor.visit_pat(&rest.arg),
+        Pat::Expr(expression) => visitor.visit_expr(expression),
+    }
+}
+
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::Ident(_) | Expr::Lit(_) | Expr::This(_) | Expr::Invalid(_) => {}
+        Expr::Member(member) => {
+            if let ExprOrSuper::Expr(object) = &member.obj {
+                visitor.visit_expr(object);
+            }
+            if member.computed {
+                visitor.visit_expr(&member.prop);
+            }
+        }
+        Expr::Call(call) => {
+            if let ExprOrSuper::Expr(callee) = &call.callee {
+                visitor.visit_expr(callee);
+            }
+            for arg in &call.args {
+                visitor.visit_expr(&arg.expr);
+            }
+        }
+        Expr::New(new_expression) => {
+            visitor.visit_expr(&new_expression.callee);
+            for arg in new_expression.args.iter().flatten() {
+                visitor.visit_expr(&arg.expr);
+            }
+        }
+        Expr::Cond(conditional) => {
+            visitor.visit_expr(&conditional.test);
+            visitor.visit_expr(&conditional.cons);
+            visitor.visit_expr(&conditional.alt);
+        }
+        Expr::Seq(sequence) => {
+            for expr in &sequence.exprs {
+                visitor.visit_expr(expr);
+            }
+        }
+        Expr::Tpl(template) => {
+            for expr in &template.exprs {
+                visitor.visit_expr(expr);
+            }
+        }
+        Expr::Arrow(arrow) => visitor.visit_arrow_expr(arrow),
+        Expr::Fn(function_expression) => visitor.visit_fn_expr(function_expression),
+        Expr::Assign(assignment) => {
+            match &assignment.left {
+                PatOrExpr::Expr(expression) => visitor.visit_expr(expression),
+                PatOrExpr::Pat(pattern) => visitor.visit_pat(pattern),
+            }
+            visitor.visit_expr(&assignment.right);
+        }
+        Expr::Bin(binary) => {
+            visitor.visit_expr(&binary.left);
+            visitor.visit_expr(&binary.right);
+        }
+        Expr::Unary(unary) => visitor.visit_expr(&unary.arg),
+        Expr::Update(update) => visitor.visit_expr(&update.arg),
+        Expr::Paren(parenthesized) => visitor.visit_expr(&parenthesized.expr),
+        Expr::Await(await_expression) => visitor.visit_expr(&await_expression.arg),
+        Expr::Yield(yield_expression) => {
+            if let Some(argument) = &yield_expression.arg {
+                visitor.visit_expr(argument);
+            }
+        }
+        Expr::Array(array) => {
+            for element in array.elems.iter().flatten() {
+                visitor.visit_expr(&element.expr);
+            }
+        }
+        Expr::TaggedTpl(tagged) => {
+            visitor.visit_expr(&tagged.tag);
+            for expr in &tagged.tpl.exprs {
+                visitor.visit_expr(expr);
+            }
+        }
+        // TODO: object literals, classes, JSX, and TS-specific expressions aren't
+        // walked yet, so an unavailable API hiding inside one of those won't be caught
+        _ => {}
+    }
+}
+
+pub fn walk_arrow_expr<V: Visitor + ?Sized>(visitor: &mut V, arrow_expr: &ArrowExpr) {
+    match &arrow_expr.body {
+        BlockStmtOrExpr::BlockStmt(block) => visitor.visit_block_stmt(block),
+        BlockStmtOrExpr::Expr(expression) => visitor.visit_expr(expression),
+    }
+}
+
+pub fn walk_fn_expr<V: Visitor + ?Sized>(visitor: &mut V, fn_expr: &FnExpr) {
+    if let Some(body) = &fn_expr.function.body {
+        visitor.visit_block_stmt(body);
+    }
+}